@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Errors produced while turning a CSV row into a `Transaction` or while
+/// applying a `Transaction` to a `Ledger`. The batch loop prints each one
+/// to STDERR and goes on with the next row.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LedgerError {
+    /// A deposit or withdrawal row was missing its `amount` column.
+    MissingAmount,
+    /// A dispute, resolve, or chargeback row carried an `amount` column.
+    AmountNotAllowed,
+    /// The `type` column didn't match any known transaction type.
+    UnknownType(String),
+    /// A withdrawal would have taken `available` below zero.
+    InsufficientFunds,
+    /// The transaction referenced by a dispute/resolve/chargeback row is
+    /// not one this ledger has recorded.
+    UnknownTransaction,
+    /// A dispute was raised against a transaction that is not currently
+    /// `Processed` (it's already disputed, resolved, or charged back).
+    AlreadyDisputed,
+    /// A resolve or chargeback was raised against a transaction that is
+    /// not currently `Disputed`.
+    NotDisputed,
+    /// The account this transaction targets is locked and rejects all
+    /// further activity.
+    AccountLocked,
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::MissingAmount => {
+                write!(f, "amount is required for this transaction type")
+            }
+            LedgerError::AmountNotAllowed => {
+                write!(f, "amount is not allowed for this transaction type")
+            }
+            LedgerError::UnknownType(type_) => write!(f, "unknown transaction type: {}", type_),
+            LedgerError::InsufficientFunds => write!(f, "insufficient available funds"),
+            LedgerError::UnknownTransaction => write!(f, "transaction is not known to this ledger"),
+            LedgerError::AlreadyDisputed => write!(f, "transaction is not in a disputable state"),
+            LedgerError::NotDisputed => write!(f, "transaction is not currently disputed"),
+            LedgerError::AccountLocked => write!(f, "account is locked"),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
@@ -0,0 +1,476 @@
+use crate::error::LedgerError;
+use crate::transaction::{Transaction, TxState};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+/// Running balance and lock status for a single client.
+///
+/// This is the ledger's internal working copy; `Account` (in
+/// `account.rs`) is the CSV-facing snapshot produced from it.
+#[derive(Debug, Clone)]
+pub struct AccountInfo {
+    pub id: u16,
+    pub available: Decimal,
+    pub held: Decimal,
+    pub total: Decimal,
+    pub locked: bool,
+}
+
+impl AccountInfo {
+    fn new(id: u16) -> AccountInfo {
+        AccountInfo {
+            id,
+            available: Decimal::new(0, 4),
+            held: Decimal::new(0, 4),
+            total: Decimal::new(0, 4),
+            locked: false,
+        }
+    }
+
+    /// Keeps every balance at a fixed scale of 4 decimal places.
+    ///
+    /// `Decimal` addition and subtraction take the larger scale of their
+    /// operands, so e.g. adding a 5-decimal-place amount widens a field's
+    /// scale from 4 to 5. Rounding and rescaling after every mutation keeps
+    /// output consistent regardless of the scale of the amounts that
+    /// produced it.
+    fn normalize(&mut self) {
+        self.available = self.available.round_dp(4);
+        self.available.rescale(4);
+        self.held = self.held.round_dp(4);
+        self.held.rescale(4);
+        self.total = self.total.round_dp(4);
+        self.total.rescale(4);
+    }
+}
+
+/// Processes transactions one at a time, without buffering the input.
+///
+/// Rather than keeping every transaction an account has ever seen, the
+/// ledger only retains the amount and lifecycle state of each
+/// deposit/withdrawal, keyed by `(client, tx)`; these entries are kept
+/// after a resolve or chargeback too. Memory therefore grows with the
+/// number of distinct accounts and deposits/withdrawals seen, while
+/// dispute, resolve and chargeback rows add nothing.
+pub struct Ledger {
+    accounts: BTreeMap<u16, AccountInfo>,
+    amounts: HashMap<(u16, u32), Decimal>,
+    tx_states: HashMap<(u16, u32), TxState>,
+}
+
+impl Ledger {
+    pub fn new() -> Ledger {
+        Ledger {
+            accounts: BTreeMap::new(),
+            amounts: HashMap::new(),
+            tx_states: HashMap::new(),
+        }
+    }
+
+    /// Applies a single transaction to the ledger.
+    ///
+    /// Once an account has been locked by a chargeback, every further
+    /// transaction for that client is rejected with `AccountLocked`
+    /// rather than being applied.
+    pub fn process(&mut self, transaction: Transaction) -> Result<(), LedgerError> {
+        let client_id = transaction.client_id();
+        if self.accounts.get(&client_id).is_some_and(|account| account.locked) {
+            return Err(LedgerError::AccountLocked);
+        }
+
+        match transaction {
+            Transaction::Deposit { client_id, id, amount } => {
+                self.deposit(client_id, id, amount)
+            }
+            Transaction::Withdrawal { client_id, id, amount } => {
+                self.withdrawal(client_id, id, amount)
+            }
+            Transaction::Dispute { client_id, id } => self.dispute(client_id, id),
+            Transaction::Resolve { client_id, id } => self.resolve(client_id, id),
+            Transaction::Chargeback { client_id, id } => self.chargeback(client_id, id),
+        }
+    }
+
+    /// Account snapshots for every client seen so far, in client id order.
+    pub fn accounts(&self) -> impl Iterator<Item = &AccountInfo> {
+        self.accounts.values()
+    }
+
+    fn account_mut(&mut self, client_id: u16) -> &mut AccountInfo {
+        self.accounts
+            .entry(client_id)
+            .or_insert_with(|| AccountInfo::new(client_id))
+    }
+
+    fn deposit(&mut self, client_id: u16, id: u32, amount: Decimal) -> Result<(), LedgerError> {
+        let account = self.account_mut(client_id);
+        account.available += amount;
+        account.total += amount;
+        account.normalize();
+        self.amounts.insert((client_id, id), amount);
+        self.tx_states.insert((client_id, id), TxState::Processed);
+        Ok(())
+    }
+
+    fn withdrawal(&mut self, client_id: u16, id: u32, amount: Decimal) -> Result<(), LedgerError> {
+        let account = self.account_mut(client_id);
+        if account.available < amount {
+            return Err(LedgerError::InsufficientFunds);
+        }
+
+        account.available -= amount;
+        account.total -= amount;
+        account.normalize();
+        self.amounts.insert((client_id, id), amount);
+        self.tx_states.insert((client_id, id), TxState::Processed);
+        Ok(())
+    }
+
+    fn dispute(&mut self, client_id: u16, id: u32) -> Result<(), LedgerError> {
+        let key = (client_id, id);
+        match self.tx_states.get(&key) {
+            Some(TxState::Processed) => {}
+            Some(_) => return Err(LedgerError::AlreadyDisputed),
+            None => return Err(LedgerError::UnknownTransaction),
+        }
+
+        let amount = *self
+            .amounts
+            .get(&key)
+            .ok_or(LedgerError::UnknownTransaction)?;
+        let account = self.account_mut(client_id);
+        account.available -= amount;
+        account.held += amount;
+        account.normalize();
+        self.tx_states.insert(key, TxState::Disputed);
+        Ok(())
+    }
+
+    fn resolve(&mut self, client_id: u16, id: u32) -> Result<(), LedgerError> {
+        let key = (client_id, id);
+        match self.tx_states.get(&key) {
+            Some(TxState::Disputed) => {}
+            Some(_) => return Err(LedgerError::NotDisputed),
+            None => return Err(LedgerError::UnknownTransaction),
+        }
+
+        let amount = *self
+            .amounts
+            .get(&key)
+            .ok_or(LedgerError::UnknownTransaction)?;
+        let account = self.account_mut(client_id);
+        account.available += amount;
+        account.held -= amount;
+        account.normalize();
+        self.tx_states.insert(key, TxState::Resolved);
+        Ok(())
+    }
+
+    fn chargeback(&mut self, client_id: u16, id: u32) -> Result<(), LedgerError> {
+        let key = (client_id, id);
+        match self.tx_states.get(&key) {
+            Some(TxState::Disputed) => {}
+            Some(_) => return Err(LedgerError::NotDisputed),
+            None => return Err(LedgerError::UnknownTransaction),
+        }
+
+        let amount = *self
+            .amounts
+            .get(&key)
+            .ok_or(LedgerError::UnknownTransaction)?;
+        let account = self.account_mut(client_id);
+        account.total -= amount;
+        account.held -= amount;
+        account.normalize();
+        account.locked = true;
+        self.tx_states.insert(key, TxState::ChargedBack);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccountInfo, Ledger};
+    use crate::error::LedgerError;
+    use crate::transaction::Transaction;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    fn account(ledger: &Ledger, client_id: u16) -> AccountInfo {
+        ledger.accounts().find(|a| a.id == client_id).unwrap().clone()
+    }
+
+    #[test]
+    fn test_deposit() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("1.25").unwrap(),
+            })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "1.2500");
+        assert_eq!(account.total.to_string(), "1.2500");
+    }
+
+    #[test]
+    fn test_withdrawal() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Withdrawal {
+                client_id: 1,
+                id: 2,
+                amount: Decimal::from_str("1.00").unwrap(),
+            })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "1.0000");
+        assert_eq!(account.total.to_string(), "1.0000");
+    }
+
+    #[test]
+    fn test_withdrawal_of_exact_balance_succeeds() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Withdrawal {
+                client_id: 1,
+                id: 2,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "0.0000");
+        assert_eq!(account.total.to_string(), "0.0000");
+    }
+
+    #[test]
+    fn test_withdrawal_with_insufficient_funds_is_rejected() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("1.00").unwrap(),
+            })
+            .unwrap();
+
+        let result = ledger.process(Transaction::Withdrawal {
+            client_id: 1,
+            id: 2,
+            amount: Decimal::from_str("2.00").unwrap(),
+        });
+
+        assert_eq!(result, Err(LedgerError::InsufficientFunds));
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "1.0000");
+    }
+
+    #[test]
+    fn test_dispute() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 2,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Dispute { client_id: 1, id: 1 })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "2.0000");
+        assert_eq!(account.held.to_string(), "3.0000");
+        assert_eq!(account.total.to_string(), "5.0000");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 2,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Dispute { client_id: 1, id: 1 })
+            .unwrap();
+        ledger
+            .process(Transaction::Resolve { client_id: 1, id: 1 })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "5.0000");
+        assert_eq!(account.held.to_string(), "0.0000");
+        assert_eq!(account.total.to_string(), "5.0000");
+    }
+
+    #[test]
+    fn test_chargeback() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 2,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Dispute { client_id: 1, id: 1 })
+            .unwrap();
+        ledger
+            .process(Transaction::Chargeback { client_id: 1, id: 1 })
+            .unwrap();
+
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "2.0000");
+        assert_eq!(account.held.to_string(), "0.0000");
+        assert_eq!(account.total.to_string(), "2.0000");
+        assert!(account.locked);
+    }
+
+    #[test]
+    fn test_resolve_without_dispute_is_rejected() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+
+        let result = ledger.process(Transaction::Resolve { client_id: 1, id: 1 });
+
+        assert_eq!(result, Err(LedgerError::NotDisputed));
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "3.0000");
+        assert_eq!(account.held.to_string(), "0.0000");
+        assert_eq!(account.total.to_string(), "3.0000");
+    }
+
+    #[test]
+    fn test_chargeback_without_dispute_is_rejected() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+
+        let result = ledger.process(Transaction::Chargeback { client_id: 1, id: 1 });
+
+        assert_eq!(result, Err(LedgerError::NotDisputed));
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "3.0000");
+        assert_eq!(account.total.to_string(), "3.0000");
+        assert!(!account.locked);
+    }
+
+    #[test]
+    fn test_dispute_twice_is_rejected() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Dispute { client_id: 1, id: 1 })
+            .unwrap();
+
+        let result = ledger.process(Transaction::Dispute { client_id: 1, id: 1 });
+
+        assert_eq!(result, Err(LedgerError::AlreadyDisputed));
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "0.0000");
+        assert_eq!(account.held.to_string(), "3.0000");
+        assert_eq!(account.total.to_string(), "3.0000");
+    }
+
+    #[test]
+    fn test_dispute_of_unknown_transaction_is_rejected() {
+        let mut ledger = Ledger::new();
+
+        let result = ledger.process(Transaction::Dispute { client_id: 1, id: 99 });
+
+        assert_eq!(result, Err(LedgerError::UnknownTransaction));
+    }
+
+    #[test]
+    fn test_locked_account_rejects_further_deposits() {
+        let mut ledger = Ledger::new();
+        ledger
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("3.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .process(Transaction::Dispute { client_id: 1, id: 1 })
+            .unwrap();
+        ledger
+            .process(Transaction::Chargeback { client_id: 1, id: 1 })
+            .unwrap();
+
+        let result = ledger.process(Transaction::Deposit {
+            client_id: 1,
+            id: 2,
+            amount: Decimal::from_str("5.00").unwrap(),
+        });
+
+        assert_eq!(result, Err(LedgerError::AccountLocked));
+        let account = account(&ledger, 1);
+        assert_eq!(account.available.to_string(), "0.0000");
+        assert_eq!(account.total.to_string(), "0.0000");
+        assert!(account.locked);
+    }
+}
@@ -1,32 +1,54 @@
 use crate::account::Account;
+use crate::ledger::Ledger;
 use csv;
-use std::collections::BTreeMap;
 use std::io;
 use structopt::StructOpt;
 use transaction::Transaction;
 
 mod account;
+mod error;
+mod ledger;
+mod server;
 mod transaction;
 
-type AccountsType = BTreeMap<u16, Account>;
-
-/// This is the struct we use to parse command line 
+/// This is the struct we use to parse command line
 /// arguments and display usage / help to the user.
+///
+/// `path` is the batch-mode input file and stays the default way to run
+/// this program; pass the `serve` subcommand instead to run as a
+/// long-lived server. See `Command`.
 #[derive(Debug, StructOpt)]
 struct Cli {
     #[structopt(parse(from_os_str))]
-    path: std::path::PathBuf,
+    path: Option<std::path::PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
 }
 
-/// reads the CSV file and creates a mapping of account
-/// id - Account objects.
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Run a long-lived TCP server that accepts streamed transactions
+    /// and answers account-summary queries against a shared, live Ledger.
+    Serve {
+        #[structopt(long, default_value = "7878")]
+        port: u16,
+    },
+}
+
+/// Streams the CSV file through the ledger one transaction at a time,
+/// so memory use grows with the number of accounts and deposits/withdrawals
+/// seen rather than with every row read.
 ///
 /// If the reader fails to parse the CSV file for any reason
 /// the application aborts.  Invalid data in the CSV file will
 /// ignore those rows and print the error to STDERR.
-fn populate_accounts(path: String, accounts: &mut AccountsType) {
+fn populate_ledger(path: String, ledger: &mut Ledger) {
+    // `flexible` allows dispute/resolve/chargeback rows to omit the
+    // trailing amount column instead of failing to parse.
     let mut reader = match csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
+        .flexible(true)
         .from_path(path)
     {
         Ok(reader) => reader,
@@ -39,10 +61,9 @@ fn populate_accounts(path: String, accounts: &mut AccountsType) {
         match result {
             Ok(result) => {
                 let transaction: Transaction = result;
-                let account = accounts
-                    .entry(transaction.client_id)
-                    .or_insert(Account::new(transaction.client_id));
-                account.add_transaction(transaction);
+                if let Err(err) = ledger.process(transaction) {
+                    eprintln!("{:?}", err);
+                }
             }
             Err(err) => {
                 eprintln!("{:?}", err)
@@ -51,22 +72,13 @@ fn populate_accounts(path: String, accounts: &mut AccountsType) {
     }
 }
 
-/// calculates the accounts available, held, total, and locked 
-/// status using the stored `account.transactions`. 
-fn process_transactions(accounts: &mut AccountsType) {
-    for account in accounts {
-        let account = account.1;
-        account.process_transactions();
-    }
-}
-
-/// Writes the account statuses to STDOUT using 
+/// Writes the account statuses to STDOUT using
 /// the serde + csv crates.
-fn write_account_summary(accounts: &AccountsType) {
+fn write_account_summary(ledger: &Ledger) {
     let mut writer = csv::Writer::from_writer(io::stdout());
 
-    for account in (accounts).values() {
-        match writer.serialize(account) {
+    for info in ledger.accounts() {
+        match writer.serialize(Account::from(info)) {
             Ok(_item) => {}
             Err(err) => {
                 eprintln!("{:?}", err);
@@ -91,17 +103,27 @@ fn write_account_summary(accounts: &AccountsType) {
 /// This is directed to STDOUT and looks like:
 /// ```csv
 /// client,available,held,total,locked
-/// 1,1.5,0.0,1.5,false
+/// 1,1.0000,0.0000,1.0000,false
 /// ```
 /// ```shell
 /// usage: cargo run --transactions.csv > accounts.csv
 /// ```
 pub async fn main() {
     let opt = Cli::from_args();
-    let filepath = opt.path.as_path().display().to_string();
-    let mut accounts: BTreeMap<u16, Account> = BTreeMap::new();
 
-    populate_accounts(filepath, &mut accounts);
-    process_transactions(&mut accounts);
-    write_account_summary(&accounts);
+    match opt.command {
+        Some(Command::Serve { port }) => {
+            if let Err(err) = server::run(port).await {
+                eprintln!("server error: {:?}", err);
+            }
+        }
+        None => {
+            let path = opt.path.expect("PATH is required unless running `serve`");
+            let filepath = path.as_path().display().to_string();
+            let mut ledger = Ledger::new();
+
+            populate_ledger(filepath, &mut ledger);
+            write_account_summary(&ledger);
+        }
+    }
 }
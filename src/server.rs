@@ -0,0 +1,199 @@
+use crate::account::Account;
+use crate::ledger::Ledger;
+use crate::transaction::Transaction;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+/// A `Ledger` shared by every connection the server accepts.
+type SharedLedger = Arc<Mutex<Ledger>>;
+
+/// Runs a long-lived TCP server on `port` that feeds incoming
+/// transactions into a single shared `Ledger` and answers account
+/// summary queries against it.
+///
+/// Each line received on a connection is either:
+///   * a transaction, as a CSV row (`type,client,tx,amount`) or a JSON
+///     object with the same fields, which is applied to the ledger; or
+///   * a query, `GET <client>` or `GET ALL`, which replies with the
+///     matching account summary as CSV.
+pub async fn run(port: u16) -> std::io::Result<()> {
+    let ledger: SharedLedger = Arc::new(Mutex::new(Ledger::new()));
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    eprintln!("listening on port {}", port);
+
+    loop {
+        let (socket, _) = listener.accept().await?;
+        let ledger = ledger.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(socket, ledger).await {
+                eprintln!("connection error: {:?}", err);
+            }
+        });
+    }
+}
+
+async fn handle_connection(socket: TcpStream, ledger: SharedLedger) -> std::io::Result<()> {
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let response = match line.strip_prefix("GET ") {
+            Some(target) => handle_query(target.trim(), &ledger).await,
+            None => handle_transaction(line, &ledger).await,
+        };
+
+        writer.write_all(response.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+/// Parses one line as a transaction and applies it to the shared ledger.
+async fn handle_transaction(line: &str, ledger: &SharedLedger) -> String {
+    match parse_transaction(line) {
+        Ok(transaction) => match ledger.lock().await.process(transaction) {
+            Ok(()) => "ok\n".to_string(),
+            Err(err) => format!("error: {}\n", err),
+        },
+        Err(err) => format!("error: {}\n", err),
+    }
+}
+
+/// Parses a single transaction from either a JSON object or a CSV row.
+fn parse_transaction(line: &str) -> Result<Transaction, String> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).map_err(|err| err.to_string());
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(line.as_bytes());
+    reader
+        .deserialize()
+        .next()
+        .ok_or_else(|| "empty transaction row".to_string())?
+        .map_err(|err| err.to_string())
+}
+
+/// Answers a `GET <client>` or `GET ALL` query with a CSV account summary.
+async fn handle_query(target: &str, ledger: &SharedLedger) -> String {
+    let ledger = ledger.lock().await;
+    let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+
+    if target.eq_ignore_ascii_case("all") {
+        for info in ledger.accounts() {
+            let _ = writer.serialize(Account::from(info));
+        }
+    } else {
+        match target.parse::<u16>() {
+            Ok(client_id) => {
+                if let Some(info) = ledger.accounts().find(|info| info.id == client_id) {
+                    let _ = writer.serialize(Account::from(info));
+                }
+            }
+            Err(_) => return format!("error: invalid client id: {}\n", target),
+        }
+    }
+
+    let _ = writer.flush();
+    String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_parse_transaction_csv_deposit() {
+        let transaction = parse_transaction("deposit,1,1,1.50").unwrap();
+        assert!(matches!(
+            transaction,
+            Transaction::Deposit { client_id: 1, id: 1, amount } if amount == Decimal::from_str("1.50").unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_transaction_csv_dispute_without_trailing_comma() {
+        let transaction = parse_transaction("dispute,1,1").unwrap();
+        assert!(matches!(
+            transaction,
+            Transaction::Dispute { client_id: 1, id: 1 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_transaction_json_deposit() {
+        let transaction =
+            parse_transaction(r#"{"type":"deposit","client":1,"tx":1,"amount":1.50}"#).unwrap();
+        assert!(matches!(
+            transaction,
+            Transaction::Deposit { client_id: 1, id: 1, amount } if amount == Decimal::from_str("1.50").unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_transaction_rejects_garbage() {
+        assert!(parse_transaction("not a transaction").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_query_single_client() {
+        let ledger: SharedLedger = Arc::new(Mutex::new(Ledger::new()));
+        ledger
+            .lock()
+            .await
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("1.50").unwrap(),
+            })
+            .unwrap();
+
+        let response = handle_query("1", &ledger).await;
+        assert!(response.contains("1,1.5000,0.0000,1.5000,false"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_query_all() {
+        let ledger: SharedLedger = Arc::new(Mutex::new(Ledger::new()));
+        ledger
+            .lock()
+            .await
+            .process(Transaction::Deposit {
+                client_id: 1,
+                id: 1,
+                amount: Decimal::from_str("1.00").unwrap(),
+            })
+            .unwrap();
+        ledger
+            .lock()
+            .await
+            .process(Transaction::Deposit {
+                client_id: 2,
+                id: 2,
+                amount: Decimal::from_str("2.00").unwrap(),
+            })
+            .unwrap();
+
+        let response = handle_query("ALL", &ledger).await;
+        assert!(response.contains("1,1.0000"));
+        assert!(response.contains("2,2.0000"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_query_rejects_invalid_client_id() {
+        let ledger: SharedLedger = Arc::new(Mutex::new(Ledger::new()));
+        let response = handle_query("not-a-client", &ledger).await;
+        assert_eq!(response, "error: invalid client id: not-a-client\n");
+    }
+}
@@ -1,58 +1,179 @@
+use crate::error::LedgerError;
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
-
-/// This calls ::Default for types that use the serde
-/// deserialize_with attribute
-fn decimal_default_if_empy<'de, D, T>(de: D) -> Result<T, D::Error>
-where
-    D: serde::Deserializer<'de>,
-    T: serde::Deserialize<'de> + Default,
-{
-    Option::<T>::deserialize(de).map(|x| x.unwrap_or_else(|| T::default()))
-}
+use serde::Deserialize;
+use std::convert::TryFrom;
 
-/// Holds information about a transaction
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Transaction {
+/// Raw shape of a single CSV row, before it's known to be well-formed.
+/// `amount` is optional here because dispute/resolve/chargeback rows
+/// omit the column entirely; `Transaction::try_from` is what enforces
+/// which types require one.
+#[derive(Debug, Clone, Deserialize)]
+struct TransactionRecord {
     #[serde(rename = "type")]
-    pub type_: String,
-    #[serde(rename = "client")]
-    pub client_id: u16,
-    #[serde(rename = "tx")]
-    pub id: u32,
-    #[serde(deserialize_with = "decimal_default_if_empy")]
-    pub amount: Decimal,
+    type_: String,
+    client: u16,
+    tx: u32,
+    /// `default` lets headerless positional rows (as used by the server's
+    /// line protocol) omit this column entirely instead of failing to
+    /// parse; a header-driven CSV file already omits it from the row.
+    #[serde(default)]
+    amount: Option<Decimal>,
+}
+
+/// Tracks where a disputable transaction sits in the
+/// dispute/resolve/chargeback lifecycle.
+///
+/// Only the following transitions are legal:
+///     `Processed -> Disputed` (on dispute)
+///     `Disputed -> Resolved` (on resolve)
+///     `Disputed -> ChargedBack` (on chargeback)
+/// Any other request (e.g. resolving a tx that was never
+/// disputed, or disputing one twice) is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxState {
+    Processed,
+    Disputed,
+    Resolved,
+    ChargedBack,
+}
+
+/// A single row of the input ledger, typed by its CSV `type` column.
+///
+/// Deserialized via `TransactionRecord` + `TryFrom` so that the amount
+/// column's presence is validated per-type at parse time instead of
+/// being defaulted away.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "TransactionRecord")]
+pub enum Transaction {
+    Deposit { client_id: u16, id: u32, amount: Decimal },
+    Withdrawal { client_id: u16, id: u32, amount: Decimal },
+    Dispute { client_id: u16, id: u32 },
+    Resolve { client_id: u16, id: u32 },
+    Chargeback { client_id: u16, id: u32 },
 }
 
 impl Transaction {
-    /// Create a new account with default values.
-    ///
-    /// Arguments:
-    ///     * id - the id for the transaction
-    /// return:
-    ///     a new Transaction object
-    ///
-    /// # example
-    /// ```rust
-    /// mod transaction;
-    /// Transaction::new(1);
-    /// ```
-    pub fn new(id: u32) -> Transaction {
-        Transaction {
-            type_: String::from(""),
-            client_id: 0,
-            id: id,
-            amount: Decimal::new(0, 4),
+    /// The client this row belongs to.
+    pub fn client_id(&self) -> u16 {
+        match self {
+            Transaction::Deposit { client_id, .. }
+            | Transaction::Withdrawal { client_id, .. }
+            | Transaction::Dispute { client_id, .. }
+            | Transaction::Resolve { client_id, .. }
+            | Transaction::Chargeback { client_id, .. } => *client_id,
+        }
+    }
+}
+
+impl TryFrom<TransactionRecord> for Transaction {
+    type Error = LedgerError;
+
+    fn try_from(record: TransactionRecord) -> Result<Self, Self::Error> {
+        match record.type_.as_str() {
+            "deposit" => Ok(Transaction::Deposit {
+                client_id: record.client,
+                id: record.tx,
+                amount: record.amount.ok_or(LedgerError::MissingAmount)?,
+            }),
+            "withdrawal" => Ok(Transaction::Withdrawal {
+                client_id: record.client,
+                id: record.tx,
+                amount: record.amount.ok_or(LedgerError::MissingAmount)?,
+            }),
+            "dispute" => {
+                if record.amount.is_some() {
+                    return Err(LedgerError::AmountNotAllowed);
+                }
+                Ok(Transaction::Dispute {
+                    client_id: record.client,
+                    id: record.tx,
+                })
+            }
+            "resolve" => {
+                if record.amount.is_some() {
+                    return Err(LedgerError::AmountNotAllowed);
+                }
+                Ok(Transaction::Resolve {
+                    client_id: record.client,
+                    id: record.tx,
+                })
+            }
+            "chargeback" => {
+                if record.amount.is_some() {
+                    return Err(LedgerError::AmountNotAllowed);
+                }
+                Ok(Transaction::Chargeback {
+                    client_id: record.client,
+                    id: record.tx,
+                })
+            }
+            other => Err(LedgerError::UnknownType(other.to_string())),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Transaction;
+    use super::{Transaction, TransactionRecord};
+    use crate::error::LedgerError;
+    use rust_decimal::Decimal;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_deposit_requires_amount() {
+        let record = TransactionRecord {
+            type_: String::from("deposit"),
+            client: 1,
+            tx: 1,
+            amount: None,
+        };
+        assert!(matches!(
+            Transaction::try_from(record),
+            Err(LedgerError::MissingAmount)
+        ));
+    }
+
+    #[test]
+    fn test_dispute_rejects_amount() {
+        let record = TransactionRecord {
+            type_: String::from("dispute"),
+            client: 1,
+            tx: 1,
+            amount: Some(Decimal::from_str("1.00").unwrap()),
+        };
+        assert!(matches!(
+            Transaction::try_from(record),
+            Err(LedgerError::AmountNotAllowed)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_type_is_rejected() {
+        let record = TransactionRecord {
+            type_: String::from("teleport"),
+            client: 1,
+            tx: 1,
+            amount: None,
+        };
+        assert!(matches!(
+            Transaction::try_from(record),
+            Err(LedgerError::UnknownType(_))
+        ));
+    }
 
     #[test]
-    fn test_new() {
-        assert_eq!(Transaction::new(1).id, 1);
+    fn test_dispute_parses_without_amount() {
+        let record = TransactionRecord {
+            type_: String::from("dispute"),
+            client: 1,
+            tx: 7,
+            amount: None,
+        };
+        let transaction = Transaction::try_from(record).unwrap();
+        assert!(matches!(
+            transaction,
+            Transaction::Dispute { client_id: 1, id: 7 }
+        ));
     }
 }